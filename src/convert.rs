@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde_json::json;
+use tokio::fs;
+
+use crate::resolve_under_root;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Self> {
+        match name {
+            "json" => Ok(Format::Json),
+            "toml" => Ok(Format::Toml),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            other => Err(anyhow!("unsupported format: {other}")),
+        }
+    }
+
+    fn from_extension(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| anyhow!("cannot infer format: {} has no extension", path.display()))?;
+        Self::parse(ext)
+    }
+}
+
+pub async fn tool_convert(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("convert.path is required"))?;
+    let to = params
+        .get("to")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("convert.to is required"))?;
+    let to = Format::parse(to)?;
+    let output = params.get("output").and_then(|v| v.as_str());
+
+    let full = resolve_under_root(root, path)?;
+    let from = match params.get("from").and_then(|v| v.as_str()) {
+        Some(name) => Format::parse(name)?,
+        None => Format::from_extension(&full)?,
+    };
+
+    let text = fs::read_to_string(&full)
+        .await
+        .map_err(|e| anyhow!("read {}: {e}", full.display()))?;
+    let value = parse_as(from, &text)?;
+    let rendered = serialize_as(to, &value)?;
+
+    match output {
+        Some(output_path) => {
+            // resolve_under_root rejects this path if it lexically escapes
+            // root, even when output_path doesn't exist yet; symlinks are
+            // only resolved (and checked) for a path that already exists.
+            let out_full = resolve_under_root(root, output_path)?;
+            if let Some(parent) = out_full.parent() {
+                fs::create_dir_all(parent).await.ok();
+            }
+            fs::write(&out_full, rendered.as_bytes())
+                .await
+                .map_err(|e| anyhow!("write {}: {e}", out_full.display()))?;
+            Ok(json!({"content": [{"type": "text", "text": format!("wrote {} bytes to {output_path}", rendered.len())}]}))
+        }
+        None => Ok(json!({"content": [{"type": "text", "text": rendered}]})),
+    }
+}
+
+fn parse_as(format: Format, text: &str) -> Result<serde_json::Value> {
+    match format {
+        Format::Json => serde_json::from_str(text).map_err(|e| anyhow!("invalid json: {e}")),
+        Format::Toml => toml::from_str(text).map_err(|e| anyhow!("invalid toml: {e}")),
+        Format::Yaml => serde_yaml::from_str(text).map_err(|e| anyhow!("invalid yaml: {e}")),
+    }
+}
+
+fn serialize_as(format: Format, value: &serde_json::Value) -> Result<String> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).map_err(|e| anyhow!("invalid json: {e}")),
+        Format::Toml => toml::to_string_pretty(value).map_err(|e| anyhow!("cannot render as toml: {e}")),
+        Format::Yaml => serde_yaml::to_string(value).map_err(|e| anyhow!("cannot render as yaml: {e}")),
+    }
+}
@@ -1,19 +1,33 @@
 use anyhow::{Context, Result, anyhow};
+use base64::Engine;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use tokio::{
-    fs,
-    io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader},
-};
+use tokio::{fs, io::AsyncWriteExt, sync::mpsc};
+
+mod convert;
+mod process;
+mod search;
+mod transport;
+mod watch;
+
+use process::Processes;
+use transport::{Framing, Transport};
+use watch::Watchers;
 
 #[derive(Parser, Debug, Clone)]
 struct Args {
     /// Restrict all file operations under this directory.
     #[arg(long, default_value = ".")]
     root: PathBuf,
+
+    /// Base protocol framing: one JSON object per line, or LSP-style
+    /// Content-Length-prefixed messages.
+    #[arg(long, value_enum, default_value = "ndjson")]
+    framing: Framing,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -53,25 +67,35 @@ struct RpcError {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    let args = Arc::new(Args::parse());
+    let framing = args.framing;
     let stdin = tokio::io::stdin();
-    let stdout = tokio::io::stdout();
-
-    let mut reader = BufReader::new(stdin);
-    let mut writer = stdout;
-
-    let mut line = String::new();
-    while reader.read_line(&mut line).await? > 0 {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            line.clear();
-            continue;
+    let mut stdout = tokio::io::stdout();
+
+    let mut transport = Transport::new(framing, stdin);
+    let watchers = Watchers::new();
+    let processes = Processes::new();
+
+    // Every request task and watcher/process notification task sends its
+    // output line here; a single writer task owns stdout so concurrent
+    // requests can never interleave mid-message.
+    let (writer, mut outbox): (watch::SharedWriter, _) = mpsc::unbounded_channel();
+    let writer_task = tokio::spawn(async move {
+        while let Some(body) = outbox.recv().await {
+            if transport::write_message(&mut stdout, framing, &body)
+                .await
+                .is_err()
+            {
+                break;
+            }
         }
+    });
 
-        let req: RpcRequest = match serde_json::from_str(trimmed) {
+    while let Some(trimmed) = transport.read_message().await? {
+        let req: RpcRequest = match serde_json::from_str(&trimmed) {
             Ok(v) => v,
             Err(e) => {
-                // If we canâ€™t parse, emit a JSON-RPC error without id.
+                // If we can't parse, emit a JSON-RPC error without id.
                 let resp = RpcResponse {
                     jsonrpc: "2.0",
                     id: None,
@@ -82,48 +106,58 @@ async fn main() -> Result<()> {
                         data: None,
                     }),
                 };
-                writer
-                    .write_all(serde_json::to_string(&resp)?.as_bytes())
-                    .await?;
-                writer.write_all(b"\n").await?;
-                writer.flush().await?;
-                line.clear();
+                let _ = writer.send(serde_json::to_string(&resp)?);
                 continue;
             }
         };
 
-        let result = handle_request(&args, &req).await;
-        let response = match result {
-            Ok(val) => RpcResponse {
-                jsonrpc: "2.0",
-                id: req.id.as_ref(),
-                result: Some(val),
-                error: None,
-            },
-            Err(err) => RpcResponse {
-                jsonrpc: "2.0",
-                id: req.id.as_ref(),
-                result: None,
-                error: Some(RpcError {
-                    code: -32000,
-                    message: err.to_string(),
-                    data: None,
-                }),
-            },
-        };
-
-        writer
-            .write_all(serde_json::to_string(&response)?.as_bytes())
-            .await?;
-        writer.write_all(b"\n").await?;
-        writer.flush().await?;
-        line.clear();
+        // Each request gets its own task so a slow tool call (a large
+        // read_file, a long search) doesn't block other in-flight requests;
+        // responses are correlated back to the caller via req.id.
+        let args = args.clone();
+        let watchers = watchers.clone();
+        let processes = processes.clone();
+        let writer = writer.clone();
+        tokio::spawn(async move {
+            let result = handle_request(&args, &req, &watchers, &processes, writer.clone()).await;
+            let response = match result {
+                Ok(val) => RpcResponse {
+                    jsonrpc: "2.0",
+                    id: req.id.as_ref(),
+                    result: Some(val),
+                    error: None,
+                },
+                Err(err) => RpcResponse {
+                    jsonrpc: "2.0",
+                    id: req.id.as_ref(),
+                    result: None,
+                    error: Some(RpcError {
+                        code: -32000,
+                        message: err.to_string(),
+                        data: None,
+                    }),
+                },
+            };
+            if let Ok(line) = serde_json::to_string(&response) {
+                let _ = writer.send(line);
+            }
+        });
     }
 
+    // Let in-flight responses drain before exiting.
+    drop(writer);
+    let _ = writer_task.await;
+
     Ok(())
 }
 
-async fn handle_request(args: &Args, req: &RpcRequest) -> Result<serde_json::Value> {
+async fn handle_request(
+    args: &Args,
+    req: &RpcRequest,
+    watchers: &Watchers,
+    processes: &Processes,
+    writer: watch::SharedWriter,
+) -> Result<serde_json::Value> {
     match req.method.as_str() {
         // MCP handshake: return server info & capabilities
         "initialize" => Ok(json!({
@@ -152,13 +186,14 @@ async fn handle_request(args: &Args, req: &RpcRequest) -> Result<serde_json::Val
             },
             {
                 "name": "read_file",
-                "description": "Read a file as UTF-8 text (relative to server root)",
+                "description": "Read a file (relative to server root); returns UTF-8 text, or base64 when the file isn't valid UTF-8",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "path": {"type": "string"},
                         "offset": {"type": "integer", "minimum": 0},
-                        "length": {"type": "integer", "minimum": 0}
+                        "length": {"type": "integer", "minimum": 0},
+                        "encoding": {"type": "string", "enum": ["utf8", "base64"]}
                     },
                     "required": ["path"],
                     "additionalProperties": false
@@ -166,31 +201,195 @@ async fn handle_request(args: &Args, req: &RpcRequest) -> Result<serde_json::Val
             },
             {
                 "name": "write_file",
-                "description": "Write UTF-8 text to a file (create or overwrite)",
+                "description": "Write to a file (create or overwrite); content is UTF-8 text by default, or base64 when encoding=\"base64\"",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "path": {"type": "string"},
                         "content": {"type": "string"},
                         "create": {"type": "boolean", "default": true},
-                        "append": {"type": "boolean", "default": false}
+                        "append": {"type": "boolean", "default": false},
+                        "encoding": {"type": "string", "enum": ["utf8", "base64"], "default": "utf8"}
                     },
                     "required": ["path", "content"],
                     "additionalProperties": false
                 }
             },
             {
-                "name": "unshare_exec",
-                "description": "Run a binary in isolated Linux namespaces using unshare",
+                "name": "convert",
+                "description": "Transcode a structured config file between JSON, TOML, and YAML (relative to server root)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "to": {"type": "string", "enum": ["json", "toml", "yaml"]},
+                        "from": {"type": "string", "enum": ["json", "toml", "yaml"]},
+                        "output": {"type": "string"}
+                    },
+                    "required": ["path", "to"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "exec_start",
+                "description": "Start a process (optionally unshare-isolated or pty-backed) and stream its output as notifications/process/output",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "binary": {"type": "string"},
-                        "args": {"type": "array", "items": {"type": "string"}}
+                        "args": {"type": "array", "items": {"type": "string"}},
+                        "unshare": {"type": "boolean", "default": false},
+                        "pty": {"type": "boolean", "default": false}
                     },
                     "required": ["binary"],
                     "additionalProperties": false
                 }
+            },
+            {
+                "name": "exec_stdin",
+                "description": "Write data to a running process's stdin",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "pid": {"type": "integer"},
+                        "data": {"type": "string"}
+                    },
+                    "required": ["pid", "data"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "exec_kill",
+                "description": "Terminate a process started with exec_start",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "pid": {"type": "integer"}
+                    },
+                    "required": ["pid"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "watch",
+                "description": "Subscribe to filesystem changes under a path (relative to server root); emits notifications/fs/changed",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "recursive": {"type": "boolean", "default": true},
+                        "debounce_ms": {"type": "integer", "minimum": 0, "default": 200}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "unwatch",
+                "description": "Cancel a subscription previously created with watch",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "watch_id": {"type": "integer"}
+                    },
+                    "required": ["watch_id"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "search",
+                "description": "Recursively search files under a path for a regex pattern (relative to server root)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "pattern": {"type": "string"},
+                        "include": {"type": "array", "items": {"type": "string"}},
+                        "exclude": {"type": "array", "items": {"type": "string"}},
+                        "case_sensitive": {"type": "boolean", "default": true},
+                        "max_results": {"type": "integer", "minimum": 1, "default": 1000},
+                        "max_file_size": {"type": "integer", "minimum": 0, "default": 10485760}
+                    },
+                    "required": ["path", "pattern"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "mkdir",
+                "description": "Create a directory under server root",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "recursive": {"type": "boolean", "default": false}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "remove",
+                "description": "Remove a file, or a directory (recursively if requested), under server root",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "recursive": {"type": "boolean", "default": false}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "copy_file",
+                "description": "Copy a file from one path to another under server root",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "from": {"type": "string"},
+                        "to": {"type": "string"}
+                    },
+                    "required": ["from", "to"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "rename",
+                "description": "Rename or move a file or directory under server root",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "from": {"type": "string"},
+                        "to": {"type": "string"}
+                    },
+                    "required": ["from", "to"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "stat",
+                "description": "Return metadata for a file or directory: size, mtime/ctime (unix millis), type, and unix mode bits",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            },
+            {
+                "name": "set_permissions",
+                "description": "Set the unix mode bits of a file or directory",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "mode": {"type": "integer", "minimum": 0}
+                    },
+                    "required": ["path", "mode"],
+                    "additionalProperties": false
+                }
             }
         ]
         })),
@@ -201,15 +400,28 @@ async fn handle_request(args: &Args, req: &RpcRequest) -> Result<serde_json::Val
                 .get("name")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| anyhow!("missing params.name"))?;
-            let args = req.params.get("arguments").cloned().unwrap_or(json!({}));
-            let root = args.get("root").and_then(|v| v.as_str()).unwrap_or("");
-            let root_path = Path::new(root);
+            let tool_args = req.params.get("arguments").cloned().unwrap_or(json!({}));
+            // The server's --root is the only root that matters; a client
+            // can't widen its own sandbox by passing a "root" argument.
+            let root_path = args.root.as_path();
 
             match name {
-                "list_dir" => tool_list_dir(&args, root_path).await,
-                "read_file" => tool_read_file(&args, root_path).await,
-                "write_file" => tool_write_file(&args, root_path).await,
-                "unshare_exec" => tool_unshare_exec(&args, root_path).await,
+                "list_dir" => tool_list_dir(&tool_args, root_path).await,
+                "read_file" => tool_read_file(&tool_args, root_path).await,
+                "write_file" => tool_write_file(&tool_args, root_path).await,
+                "exec_start" => processes.exec_start(&tool_args, root_path, writer.clone()).await,
+                "exec_stdin" => processes.exec_stdin(&tool_args).await,
+                "exec_kill" => processes.exec_kill(&tool_args).await,
+                "watch" => watchers.watch(root_path, &tool_args, writer.clone()).await,
+                "unwatch" => watchers.unwatch(&tool_args).await,
+                "search" => search::tool_search(&tool_args, root_path).await,
+                "mkdir" => tool_mkdir(&tool_args, root_path).await,
+                "remove" => tool_remove(&tool_args, root_path).await,
+                "copy_file" => tool_copy_file(&tool_args, root_path).await,
+                "rename" => tool_rename(&tool_args, root_path).await,
+                "stat" => tool_stat(&tool_args, root_path).await,
+                "set_permissions" => tool_set_permissions(&tool_args, root_path).await,
+                "convert" => convert::tool_convert(&tool_args, root_path).await,
                 other => Err(anyhow!("Unknown tool: {other}")),
             }
         }
@@ -223,58 +435,52 @@ async fn handle_request(args: &Args, req: &RpcRequest) -> Result<serde_json::Val
     }
 }
 
-async fn tool_unshare_exec(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
-    let binary = params.get("binary").and_then(|v| v.as_str()).ok_or_else(|| anyhow!("binary required"))?;
-    let args = params.get("args").and_then(|v| v.as_array())
-        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
-        .unwrap_or_default();
-
-    // Optional: map working directory under root
-    let cwd = root.join("sandbox");
-    tokio::fs::create_dir_all(&cwd).await?;
-
-    // Spawn unshare command
-    let output = std::process::Command::new("unshare")
-        .arg("--uts")
-        .arg("--ipc")
-        .arg("--net")
-        .arg("--pid")
-        .arg("--fork")
-        .arg("--user")
-        .arg(binary)
-        .args(&args)
-        .current_dir(&cwd)
-        .output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let exit_code = output.status.code().unwrap_or(-1);
-
-    Ok(serde_json::json!({
-        "stdout": stdout,
-        "stderr": stderr,
-        "exit_code": exit_code
-    }))
-}
-
-fn resolve_under_root(root: &Path, rel: &str) -> Result<PathBuf> {
+pub(crate) fn resolve_under_root(root: &Path, rel: &str) -> Result<PathBuf> {
     let base = root.canonicalize().unwrap_or_else(|_| root.to_path_buf()); // fallback if root doesn't exist
-    let joined = base.join(rel);
+    // Collapse `.`/`..` lexically first: a non-existent path never reaches
+    // canonicalize() below, so an un-normalized `../../etc` would otherwise
+    // pass the starts_with check on its literal (unresolved) components.
+    let joined = normalize_lexically(&base.join(rel));
+
+    if !joined.starts_with(&base) {
+        anyhow::bail!("path escapes root: {}", joined.display());
+    }
 
-    // Only canonicalize if path exists
+    // Only canonicalize if path exists, to additionally resolve symlinks
+    // against the real filesystem root.
     let canonical = if joined.exists() {
-        joined.canonicalize()?
+        let resolved = joined.canonicalize()?;
+        if !resolved.starts_with(&base) {
+            anyhow::bail!("path escapes root: {}", resolved.display());
+        }
+        resolved
     } else {
         joined
     };
 
-    if !canonical.starts_with(&base) {
-        anyhow::bail!("path escapes root: {}", canonical.display());
-    }
-
     Ok(canonical)
 }
 
+/// Collapses `.` and `..` components without touching the filesystem, so a
+/// path can be checked against a root before it's known to exist.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut stack: Vec<std::path::Component> = Vec::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if matches!(stack.last(), Some(std::path::Component::Normal(_))) {
+                    stack.pop();
+                } else {
+                    stack.push(component);
+                }
+            }
+            other => stack.push(other),
+        }
+    }
+    stack.iter().collect()
+}
+
 async fn tool_list_dir(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
     let path = params
         .get("path")
@@ -321,6 +527,7 @@ async fn tool_read_file(params: &serde_json::Value, root: &Path) -> Result<serde
 
     let offset = params.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
     let length = params.get("length").and_then(|v| v.as_u64());
+    let encoding = params.get("encoding").and_then(|v| v.as_str());
 
     let full = resolve_under_root(root, path)?;
     let data = fs::read(&full)
@@ -333,11 +540,28 @@ async fn tool_read_file(params: &serde_json::Value, root: &Path) -> Result<serde
     } else {
         &data[offset.min(data.len())..]
     };
-    let text = String::from_utf8_lossy(slice).to_string();
 
-    Ok(json!({
-    "content": [{"type": "text", "text": text }]
-    }))
+    // Binary files are not valid UTF-8, so round-trip them as base64 rather
+    // than corrupting them with a lossy conversion.
+    match encoding {
+        Some("base64") => Ok(json!({
+            "content": [{"type": "text", "text": base64::engine::general_purpose::STANDARD.encode(slice) }],
+            "encoding": "base64"
+        })),
+        Some("utf8") => {
+            let text = std::str::from_utf8(slice)
+                .map_err(|_| anyhow!("{path} is not valid utf8; read with encoding=\"base64\""))?;
+            Ok(json!({"content": [{"type": "text", "text": text }], "encoding": "utf8"}))
+        }
+        Some(other) => Err(anyhow!("unknown encoding: {other}")),
+        None => match std::str::from_utf8(slice) {
+            Ok(text) => Ok(json!({"content": [{"type": "text", "text": text }], "encoding": "utf8"})),
+            Err(_) => Ok(json!({
+                "content": [{"type": "text", "text": base64::engine::general_purpose::STANDARD.encode(slice) }],
+                "encoding": "base64"
+            })),
+        },
+    }
 }
 
 async fn tool_write_file(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
@@ -353,6 +577,15 @@ async fn tool_write_file(params: &serde_json::Value, root: &Path) -> Result<serd
         .get("append")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let encoding = params.get("encoding").and_then(|v| v.as_str()).unwrap_or("utf8");
+
+    let bytes: Vec<u8> = match encoding {
+        "utf8" => content.as_bytes().to_vec(),
+        "base64" => base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| anyhow!("invalid base64 content: {e}"))?,
+        other => return Err(anyhow!("unknown encoding: {other}")),
+    };
 
     let full = resolve_under_root(root, path)?;
     if let Some(parent) = full.parent() {
@@ -365,11 +598,161 @@ async fn tool_write_file(params: &serde_json::Value, root: &Path) -> Result<serd
             .append(true)
             .open(&full)
             .await?;
-        f.write_all(content.as_bytes()).await?;
+        f.write_all(&bytes).await?;
     } else {
-        fs::write(&full, content.as_bytes()).await?;
+        fs::write(&full, &bytes).await?;
     }
 
     Ok(json!({
-"content": [{"type": "text", "text": format!("wrote {} bytes to {}", content.len(), path)}]}))
+"content": [{"type": "text", "text": format!("wrote {} bytes to {}", bytes.len(), path)}]}))
+}
+
+async fn tool_mkdir(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("mkdir.path is required"))?;
+    let recursive = params
+        .get("recursive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let full = resolve_under_root(root, path)?;
+    if recursive {
+        fs::create_dir_all(&full).await
+    } else {
+        fs::create_dir(&full).await
+    }
+    .with_context(|| format!("mkdir {}", full.display()))?;
+
+    Ok(json!({"content": [{"type": "text", "text": format!("created {path}")}]}))
+}
+
+async fn tool_remove(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("remove.path is required"))?;
+    let recursive = params
+        .get("recursive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let full = resolve_under_root(root, path)?;
+    let md = fs::metadata(&full)
+        .await
+        .with_context(|| format!("stat {}", full.display()))?;
+
+    if md.is_dir() {
+        if recursive {
+            fs::remove_dir_all(&full).await
+        } else {
+            fs::remove_dir(&full).await
+        }
+    } else {
+        fs::remove_file(&full).await
+    }
+    .with_context(|| format!("remove {}", full.display()))?;
+
+    Ok(json!({"content": [{"type": "text", "text": format!("removed {path}")}]}))
+}
+
+async fn tool_copy_file(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let from = params
+        .get("from")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("copy_file.from is required"))?;
+    let to = params
+        .get("to")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("copy_file.to is required"))?;
+
+    let src = resolve_under_root(root, from)?;
+    let dst = resolve_under_root(root, to)?;
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent).await.ok();
+    }
+
+    let bytes = fs::copy(&src, &dst)
+        .await
+        .with_context(|| format!("copy {} to {}", src.display(), dst.display()))?;
+
+    Ok(json!({"content": [{"type": "text", "text": format!("copied {bytes} bytes to {to}")}]}))
+}
+
+async fn tool_rename(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let from = params
+        .get("from")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("rename.from is required"))?;
+    let to = params
+        .get("to")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("rename.to is required"))?;
+
+    let src = resolve_under_root(root, from)?;
+    let dst = resolve_under_root(root, to)?;
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent).await.ok();
+    }
+
+    fs::rename(&src, &dst)
+        .await
+        .with_context(|| format!("rename {} to {}", src.display(), dst.display()))?;
+
+    Ok(json!({"content": [{"type": "text", "text": format!("renamed {from} to {to}")}]}))
+}
+
+async fn tool_stat(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("stat.path is required"))?;
+
+    let full = resolve_under_root(root, path)?;
+    let md = fs::symlink_metadata(&full)
+        .await
+        .with_context(|| format!("stat {}", full.display()))?;
+    // resolve_under_root follows symlinks, so `full` is never itself a link;
+    // check the unresolved path to report is_symlink.
+    let base = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let is_symlink = fs::symlink_metadata(normalize_lexically(&base.join(path)))
+        .await
+        .map(|m| m.is_symlink())
+        .unwrap_or(false);
+
+    Ok(json!({
+        "content": [{"type": "json", "json": {
+            "path": path,
+            "len": md.len(),
+            "is_dir": md.is_dir(),
+            "is_file": md.is_file(),
+            "is_symlink": is_symlink,
+            "mtime_ms": md.mtime() * 1000 + md.mtime_nsec() / 1_000_000,
+            "ctime_ms": md.ctime() * 1000 + md.ctime_nsec() / 1_000_000,
+            "mode": md.permissions().mode(),
+        }}]
+    }))
+}
+
+async fn tool_set_permissions(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("set_permissions.path is required"))?;
+    let mode = params
+        .get("mode")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow!("set_permissions.mode is required"))? as u32;
+
+    let full = resolve_under_root(root, path)?;
+    fs::set_permissions(&full, std::fs::Permissions::from_mode(mode))
+        .await
+        .with_context(|| format!("set_permissions {}", full.display()))?;
+
+    Ok(json!({"content": [{"type": "text", "text": format!("set mode {mode:o} on {path}")}]}))
 }
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde_json::json;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::watch::SharedWriter;
+
+pub type ProcessId = u64;
+
+enum ProcessHandle {
+    Piped {
+        stdin: mpsc::UnboundedSender<Vec<u8>>,
+        child: Arc<Mutex<tokio::process::Child>>,
+    },
+    Pty {
+        stdin: mpsc::UnboundedSender<Vec<u8>>,
+        child: Arc<Mutex<Box<dyn portable_pty::Child + Send + Sync>>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Processes {
+    handles: Arc<Mutex<HashMap<ProcessId, ProcessHandle>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Processes {
+    pub fn new() -> Self {
+        Self {
+            handles: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    pub async fn exec_start(
+        &self,
+        params: &serde_json::Value,
+        root: &Path,
+        writer: SharedWriter,
+    ) -> Result<serde_json::Value> {
+        let binary = params
+            .get("binary")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("exec_start.binary is required"))?;
+        let args: Vec<String> = params
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let use_unshare = params
+            .get("unshare")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let use_pty = params.get("pty").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let cwd = root.join("sandbox");
+        tokio::fs::create_dir_all(&cwd).await.ok();
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        if use_pty {
+            self.spawn_pty(id, binary, &args, &cwd, use_unshare, writer)
+                .await
+        } else {
+            self.spawn_piped(id, binary, &args, &cwd, use_unshare, writer)
+                .await
+        }
+    }
+
+    async fn spawn_piped(
+        &self,
+        id: ProcessId,
+        binary: &str,
+        args: &[String],
+        cwd: &Path,
+        use_unshare: bool,
+        writer: SharedWriter,
+    ) -> Result<serde_json::Value> {
+        let mut cmd = if use_unshare {
+            let mut c = Command::new("unshare");
+            c.args(["--uts", "--ipc", "--net", "--pid", "--fork", "--user"])
+                .arg(binary)
+                .args(args);
+            c
+        } else {
+            let mut c = Command::new(binary);
+            c.args(args);
+            c
+        };
+        cmd.current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow!("failed to spawn {binary}: {e}"))?;
+
+        let stdout = child.stdout.take().expect("piped stdout");
+        let stderr = child.stderr.take().expect("piped stderr");
+        let mut child_stdin = child.stdin.take().expect("piped stdin");
+
+        let (stdin_tx, mut stdin_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(async move {
+            while let Some(bytes) = stdin_rx.recv().await {
+                if child_stdin.write_all(&bytes).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let stdout_task = spawn_stream_reader(id, "stdout", stdout, writer.clone());
+        let stderr_task = spawn_stream_reader(id, "stderr", stderr, writer.clone());
+
+        let child = Arc::new(Mutex::new(child));
+        let wait_child = child.clone();
+        let exit_writer = writer.clone();
+        let handles = self.handles.clone();
+        tokio::spawn(async move {
+            let _ = stdout_task.await;
+            let _ = stderr_task.await;
+            let status = wait_child.lock().await.wait().await;
+            let code = status.ok().and_then(|s| s.code()).unwrap_or(-1);
+            send_notification(
+                &exit_writer,
+                "notifications/process/exit",
+                json!({"pid": id, "exit_code": code}),
+            );
+            // A process that exits on its own is never told to `exec_kill`,
+            // so deregister ourselves instead of leaking the entry forever.
+            handles.lock().await.remove(&id);
+        });
+
+        self.handles
+            .lock()
+            .await
+            .insert(id, ProcessHandle::Piped { stdin: stdin_tx, child });
+
+        Ok(json!({"content": [{"type": "json", "json": {"pid": id}}]}))
+    }
+
+    async fn spawn_pty(
+        &self,
+        id: ProcessId,
+        binary: &str,
+        args: &[String],
+        cwd: &Path,
+        use_unshare: bool,
+        writer: SharedWriter,
+    ) -> Result<serde_json::Value> {
+        use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+
+        let pty_system = native_pty_system();
+        let pair = pty_system.openpty(PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let mut builder = if use_unshare {
+            let mut b = CommandBuilder::new("unshare");
+            for a in ["--uts", "--ipc", "--net", "--pid", "--fork", "--user", binary] {
+                b.arg(a);
+            }
+            b
+        } else {
+            CommandBuilder::new(binary)
+        };
+        for a in args {
+            builder.arg(a);
+        }
+        builder.cwd(cwd);
+
+        let child = pair
+            .slave
+            .spawn_command(builder)
+            .map_err(|e| anyhow!("failed to spawn {binary} under pty: {e}"))?;
+        drop(pair.slave);
+
+        let mut pty_reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| anyhow!("pty reader: {e}"))?;
+        let mut pty_writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| anyhow!("pty writer: {e}"))?;
+
+        let (stdin_tx, mut stdin_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::task::spawn_blocking(move || {
+            use std::io::Write;
+            while let Some(bytes) = stdin_rx.blocking_recv() {
+                if pty_writer.write_all(&bytes).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let read_writer = writer.clone();
+        let read_task: JoinHandle<()> = tokio::task::spawn_blocking(move || {
+            use std::io::Read;
+            let mut buf = [0u8; 4096];
+            loop {
+                match pty_reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let encoded = base64::engine::general_purpose::STANDARD.encode(&buf[..n]);
+                        send_notification(
+                            &read_writer,
+                            "notifications/process/output",
+                            json!({"pid": id, "stream": "stdout", "data": encoded}),
+                        );
+                    }
+                }
+            }
+        });
+
+        let child = Arc::new(Mutex::new(child));
+        let wait_child = child.clone();
+        let exit_writer = writer.clone();
+        let handles = self.handles.clone();
+        tokio::spawn(async move {
+            let _ = read_task.await;
+            let code = tokio::task::spawn_blocking(move || {
+                // Safe to block here: the reader above has already hit EOF,
+                // so the child has exited or is exiting imminently.
+                blocking_wait_pty(wait_child)
+            })
+            .await
+            .unwrap_or(-1);
+            send_notification(
+                &exit_writer,
+                "notifications/process/exit",
+                json!({"pid": id, "exit_code": code}),
+            );
+            // Same self-deregistration as the piped path: a process that
+            // exits on its own is never told to `exec_kill`.
+            handles.lock().await.remove(&id);
+        });
+
+        self.handles
+            .lock()
+            .await
+            .insert(id, ProcessHandle::Pty { stdin: stdin_tx, child });
+
+        Ok(json!({"content": [{"type": "json", "json": {"pid": id, "pty": true}}]}))
+    }
+
+    pub async fn exec_stdin(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
+        let pid = params
+            .get("pid")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("exec_stdin.pid is required"))?;
+        let data = params
+            .get("data")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("exec_stdin.data is required"))?;
+
+        let handles = self.handles.lock().await;
+        let handle = handles
+            .get(&pid)
+            .ok_or_else(|| anyhow!("no such pid: {pid}"))?;
+        let tx = match handle {
+            ProcessHandle::Piped { stdin, .. } => stdin,
+            ProcessHandle::Pty { stdin, .. } => stdin,
+        };
+        tx.send(data.as_bytes().to_vec())
+            .map_err(|_| anyhow!("process {pid} stdin closed"))?;
+
+        Ok(json!({"content": [{"type": "text", "text": format!("wrote {} bytes to pid {pid} stdin", data.len())}]}))
+    }
+
+    pub async fn exec_kill(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
+        let pid = params
+            .get("pid")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("exec_kill.pid is required"))?;
+
+        let handle = self
+            .handles
+            .lock()
+            .await
+            .remove(&pid)
+            .ok_or_else(|| anyhow!("no such pid: {pid}"))?;
+
+        match handle {
+            ProcessHandle::Piped { child, .. } => {
+                child.lock().await.start_kill().ok();
+            }
+            ProcessHandle::Pty { child, .. } => {
+                child.lock().await.kill().ok();
+            }
+        }
+
+        Ok(json!({"content": [{"type": "text", "text": format!("killed pid {pid}")}]}))
+    }
+}
+
+/// Blocks on the portable-pty child's synchronous `wait()`, returning the
+/// exit code (or -1 if it can't be determined). Runs inside
+/// `spawn_blocking`, never on the async executor.
+fn blocking_wait_pty(child: Arc<Mutex<Box<dyn portable_pty::Child + Send + Sync>>>) -> i32 {
+    let mut guard = child.blocking_lock();
+    guard
+        .wait()
+        .ok()
+        .map(|status| status.exit_code() as i32)
+        .unwrap_or(-1)
+}
+
+fn spawn_stream_reader<R>(
+    pid: ProcessId,
+    stream: &'static str,
+    mut reader: R,
+    writer: SharedWriter,
+) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let encoded = base64::engine::general_purpose::STANDARD.encode(&buf[..n]);
+                    send_notification(
+                        &writer,
+                        "notifications/process/output",
+                        json!({"pid": pid, "stream": stream, "data": encoded}),
+                    );
+                }
+            }
+        }
+    })
+}
+
+fn send_notification(writer: &SharedWriter, method: &str, params: serde_json::Value) {
+    let notif = json!({"jsonrpc": "2.0", "method": method, "params": params});
+    let line = serde_json::to_string(&notif).unwrap_or_default();
+    let _ = writer.send(line);
+}
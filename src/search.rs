@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use glob::Pattern;
+use regex::{Regex, RegexBuilder};
+use serde_json::json;
+use tokio::fs;
+
+use crate::resolve_under_root;
+
+const DEFAULT_MAX_RESULTS: usize = 1000;
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+pub async fn tool_search(params: &serde_json::Value, root: &Path) -> Result<serde_json::Value> {
+    let path = params
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("search.path is required"))?;
+    let pattern = params
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("search.pattern is required"))?;
+    let case_sensitive = params
+        .get("case_sensitive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let max_results = params
+        .get("max_results")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_MAX_RESULTS as u64) as usize;
+    let max_file_size = params
+        .get("max_file_size")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    let include = parse_globs(params.get("include"))?;
+    let exclude = parse_globs(params.get("exclude"))?;
+
+    // Carry the case flag inline in the pattern, so the byte-level regex
+    // that search_file rebuilds from `regex.as_str()` honours it too.
+    let source = if case_sensitive {
+        pattern.to_string()
+    } else {
+        format!("(?i){pattern}")
+    };
+    let regex = RegexBuilder::new(&source)
+        .build()
+        .map_err(|e| anyhow!("invalid regex {pattern}: {e}"))?;
+
+    let base = resolve_under_root(root, path)?;
+    let anchor = base.canonicalize().unwrap_or_else(|_| base.clone());
+
+    let mut matches = Vec::new();
+    let mut stack = vec![base];
+    'walk: while let Some(dir) = stack.pop() {
+        let mut rd = match fs::read_dir(&dir).await {
+            Ok(rd) => rd,
+            Err(_) => continue,
+        };
+        while let Some(entry) = rd.next_entry().await? {
+            let entry_path = entry.path();
+            let md = match fs::symlink_metadata(&entry_path).await {
+                Ok(md) => md,
+                Err(_) => continue,
+            };
+
+            // For a symlink, only follow it if its target resolves under
+            // the search path (and so under root), and from then on treat the *target's* metadata/path as
+            // the thing being walked (symlink_metadata never reports a
+            // symlink itself as a dir or file).
+            let (walk_path, walk_md) = if md.is_symlink() {
+                let resolved = match fs::canonicalize(&entry_path).await {
+                    Ok(p) => p,
+                    Err(_) => continue,
+                };
+                if !resolved.starts_with(&anchor) {
+                    continue;
+                }
+                let target_md = match fs::metadata(&resolved).await {
+                    Ok(m) => m,
+                    Err(_) => continue,
+                };
+                (resolved, target_md)
+            } else {
+                (entry_path, md)
+            };
+
+            if walk_md.is_dir() {
+                stack.push(walk_path);
+                continue;
+            }
+
+            if !walk_md.is_file() {
+                continue;
+            }
+
+            if passes_filters(&walk_path, &anchor, &include, &exclude) {
+                if walk_md.len() > max_file_size {
+                    continue;
+                }
+                if search_file(&walk_path, &regex, max_results, &mut matches).await? {
+                    break 'walk;
+                }
+            }
+        }
+    }
+
+    Ok(json!({
+        "content": [{"type": "json", "json": matches}]
+    }))
+}
+
+fn parse_globs(value: Option<&serde_json::Value>) -> Result<Vec<Pattern>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let Some(arr) = value.as_array() else {
+        return Ok(Vec::new());
+    };
+    arr.iter()
+        .filter_map(|v| v.as_str())
+        .map(|s| Pattern::new(s).map_err(|e| anyhow!("invalid glob {s}: {e}")))
+        .collect()
+}
+
+fn passes_filters(path: &Path, anchor: &Path, include: &[Pattern], exclude: &[Pattern]) -> bool {
+    // Match glob filters against the path relative to the search root, since
+    // a caller has no way to know (or reason to know) the server's absolute
+    // on-disk root.
+    let relative = path.strip_prefix(anchor).unwrap_or(path).display().to_string();
+    if exclude.iter().any(|p| p.matches(&relative)) {
+        return false;
+    }
+    include.is_empty() || include.iter().any(|p| p.matches(&relative))
+}
+
+/// Scans a single file for matches, appending to `matches`. Returns `true`
+/// once `max_results` has been reached so the caller can stop walking.
+async fn search_file(
+    path: &Path,
+    regex: &Regex,
+    max_results: usize,
+    matches: &mut Vec<serde_json::Value>,
+) -> Result<bool> {
+    let data = match fs::read(path).await {
+        Ok(data) => data,
+        Err(_) => return Ok(false),
+    };
+
+    match std::str::from_utf8(&data) {
+        Ok(text) => {
+            for (line_number, line) in text.lines().enumerate() {
+                for m in regex.find_iter(line) {
+                    matches.push(json!({
+                        "path": path.display().to_string(),
+                        "line_number": line_number + 1,
+                        "start": m.start(),
+                        "end": m.end(),
+                        "match": {"type": "text", "value": m.as_str()},
+                    }));
+                    if matches.len() >= max_results {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        Err(_) => {
+            // Not valid UTF-8 as a whole: match over raw bytes and inline
+            // the result as base64, same as distant does for binary content.
+            let bytes_regex = regex::bytes::Regex::new(regex.as_str())?;
+            for m in bytes_regex.find_iter(&data) {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(m.as_bytes());
+                matches.push(json!({
+                    "path": path.display().to_string(),
+                    "line_number": serde_json::Value::Null,
+                    "start": m.start(),
+                    "end": m.end(),
+                    "match": {"type": "bytes", "value": encoded},
+                }));
+                if matches.len() >= max_results {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,107 @@
+use std::io;
+
+use clap::ValueEnum;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Upper bound on a single Content-Length-framed message, to keep a
+/// malformed or hostile header from forcing an arbitrarily large allocation
+/// before any body bytes are even read.
+const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+/// Which base protocol framing a connection speaks. `Ndjson` is the
+/// original one-json-object-per-line format; `ContentLength` is the LSP
+/// base protocol (`Content-Length: N\r\n\r\n` followed by N bytes), which
+/// tolerates embedded newlines and large payloads.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum Framing {
+    #[default]
+    Ndjson,
+    ContentLength,
+}
+
+pub struct Transport<R> {
+    framing: Framing,
+    reader: BufReader<R>,
+}
+
+impl<R: AsyncRead + Unpin> Transport<R> {
+    pub fn new(framing: Framing, inner: R) -> Self {
+        Self {
+            framing,
+            reader: BufReader::new(inner),
+        }
+    }
+
+    /// Reads the next message body, or `None` at EOF.
+    pub async fn read_message(&mut self) -> io::Result<Option<String>> {
+        match self.framing {
+            Framing::Ndjson => self.read_ndjson().await,
+            Framing::ContentLength => self.read_content_length().await,
+        }
+    }
+
+    async fn read_ndjson(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                return Ok(Some(trimmed.to_string()));
+            }
+        }
+    }
+
+    async fn read_content_length(&mut self) -> io::Result<Option<String>> {
+        let mut content_length: Option<usize> = None;
+        loop {
+            let mut header = String::new();
+            if self.reader.read_line(&mut header).await? == 0 {
+                return Ok(None);
+            }
+            let header = header.trim_end_matches(['\r', '\n']);
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+
+        let len = content_length.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+        })?;
+        if len > MAX_MESSAGE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Content-Length {len} exceeds max of {MAX_MESSAGE_LEN}"),
+            ));
+        }
+        let mut body = vec![0u8; len];
+        self.reader.read_exact(&mut body).await?;
+        Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+    }
+}
+
+/// Writes one message body to `writer` using `framing`, flushing afterward
+/// so the peer sees it immediately.
+pub async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    framing: Framing,
+    body: &str,
+) -> io::Result<()> {
+    match framing {
+        Framing::Ndjson => {
+            writer.write_all(body.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+        }
+        Framing::ContentLength => {
+            let header = format!("Content-Length: {}\r\n\r\n", body.len());
+            writer.write_all(header.as_bytes()).await?;
+            writer.write_all(body.as_bytes()).await?;
+        }
+    }
+    writer.flush().await
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::json;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::resolve_under_root;
+
+/// stdout is owned by a single writer task; every request task and watcher/
+/// process task sends its output lines through this channel so JSON-RPC
+/// messages never interleave mid-line.
+pub type SharedWriter = mpsc::UnboundedSender<String>;
+
+pub type WatchId = u64;
+
+#[derive(Clone)]
+pub struct Watchers {
+    tasks: Arc<Mutex<HashMap<WatchId, JoinHandle<()>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Watchers {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    pub async fn watch(
+        &self,
+        root: &Path,
+        params: &serde_json::Value,
+        writer: SharedWriter,
+    ) -> Result<serde_json::Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("watch.path is required"))?;
+        let recursive = params
+            .get("recursive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let debounce_ms = params
+            .get("debounce_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(200);
+
+        let target = resolve_under_root(root, path)?;
+        let watch_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel::<notify::Result<Event>>();
+        let mut watcher: RecommendedWatcher =
+            notify::recommended_watcher(move |res| {
+                let _ = tx.send(res);
+            })?;
+        watcher.watch(&target, mode)?;
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let debounce = Duration::from_millis(debounce_ms.max(1));
+
+        let tasks = self.tasks.clone();
+        let handle = tokio::spawn(async move {
+            run_watch_loop(watcher, rx, debounce, id, watch_root, writer).await;
+            // The loop above only returns once the notify channel or the
+            // writer has closed, so deregister ourselves instead of relying
+            // on an explicit unwatch that may never come.
+            tasks.lock().await.remove(&id);
+        });
+
+        self.tasks.lock().await.insert(id, handle);
+
+        Ok(json!({"content": [{"type": "json", "json": {"watch_id": id}}]}))
+    }
+
+    pub async fn unwatch(&self, params: &serde_json::Value) -> Result<serde_json::Value> {
+        let id = params
+            .get("watch_id")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("unwatch.watch_id is required"))?;
+
+        match self.tasks.lock().await.remove(&id) {
+            Some(handle) => {
+                handle.abort();
+                Ok(json!({"content": [{"type": "text", "text": format!("stopped watch {id}")}]}))
+            }
+            None => Err(anyhow!("no such watch_id: {id}")),
+        }
+    }
+}
+
+async fn run_watch_loop(
+    watcher: RecommendedWatcher,
+    mut rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
+    debounce: Duration,
+    id: WatchId,
+    watch_root: PathBuf,
+    writer: SharedWriter,
+) {
+    // Keep the watcher alive for as long as this loop runs.
+    let _watcher = watcher;
+    let mut pending: HashMap<PathBuf, &'static str> = HashMap::new();
+
+    while let Some(first) = rx.recv().await {
+        if let Ok(event) = first {
+            coalesce(&mut pending, &event, &watch_root);
+        }
+
+        // Drain whatever else arrives within the debounce window so a
+        // burst of events collapses into one notification per path.
+        let deadline = tokio::time::Instant::now() + debounce;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some(Ok(event))) => coalesce(&mut pending, &event, &watch_root),
+                Ok(Some(Err(_))) => {}
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        for (path, kind) in pending.drain() {
+            let notif = json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/fs/changed",
+                "params": {
+                    "watch_id": id,
+                    "path": path.display().to_string(),
+                    "kind": kind,
+                }
+            });
+            let line = serde_json::to_string(&notif).unwrap_or_default();
+            if writer.send(line).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn coalesce(pending: &mut HashMap<PathBuf, &'static str>, event: &Event, root: &Path) {
+    let kind = match &event.kind {
+        EventKind::Create(_) => "created",
+        EventKind::Remove(_) => "removed",
+        EventKind::Modify(ModifyKind::Name(_)) => "renamed",
+        EventKind::Modify(_) => "modified",
+        _ => return,
+    };
+    for path in &event.paths {
+        // A path that canonicalizes outside root (e.g. a symlink target) is
+        // not ours to report. A removed path can no longer be
+        // canonicalized, so it is checked as reported.
+        let resolved = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !resolved.starts_with(root) {
+            continue;
+        }
+        pending.insert(path.clone(), kind);
+    }
+}